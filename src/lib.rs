@@ -18,6 +18,7 @@ use core::u32;
 pub struct RiffReader<'a> {
     data: &'a [u8], // Underlying RIFF-encoded byte slice
     pos: usize,     // Index of next byte in the byte slice that should be read
+    endianness: Endianness,
 }
 
 /// RiffError is returned when invalid data is encountered or an end-of-underlying-data-pool is reached.
@@ -31,6 +32,8 @@ pub enum RiffError {
     EndOfData,
     /// Unexpected end of byte slice reached (chunk length is greater than remaining number of bytes)
     UnexpectedEndOfData(usize, u32, usize),
+    /// The outer chunk is not a well-formed `RIFF`/`RIFX` container
+    NotARiffContainer,
 }
 
 impl fmt::Display for RiffError {
@@ -45,10 +48,20 @@ impl fmt::Display for RiffError {
             InvalidIDNotASCII => write!(f, "Supplied ID is not valid ASCII"),
             EndOfData => write!(f, "End of the underlying byte slice reached"),
             UnexpectedEndOfData(len_pos, expected, have) => write!(f, "Expected {} bytes of data based on the index at position {}, however only {} are left", expected, len_pos, have),
+            NotARiffContainer => write!(f, "Outer chunk is not a well-formed RIFF/RIFX container"),
         }
     }
 }
 
+/// The byte order used to decode the 4-byte length field of each chunk.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Endianness {
+    /// Little-endian lengths, as used by the common `RIFF` variant.
+    Little,
+    /// Big-endian lengths, as used by the `RIFX` variant (some AIFF/authoring tools).
+    Big,
+}
+
 /// A RIFF chunk.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Chunk<'a> {
@@ -58,9 +71,61 @@ pub struct Chunk<'a> {
     pub id: ChunkId,
     /// The length of the data in the chunk
     pub len: usize,
+    // Endianness inherited from the `RiffReader` that produced this chunk, so that
+    // `subchunks()` can keep decoding nested chunk lengths the same way.
+    endianness: Endianness,
 }
 
-/// TODO: Implement turing Chunk into RiffReader for recursion-capable chunks
+/// The chunk ID of a top-level little-endian RIFF container.
+pub const RIFF_ID: ChunkId = ChunkId { id: *b"RIFF" };
+
+/// The chunk ID of a top-level big-endian RIFF container (see `Endianness::Big`).
+pub const RIFX_ID: ChunkId = ChunkId { id: *b"RIFX" };
+
+/// The chunk ID of a RIFF `LIST` container.
+pub const LIST_ID: ChunkId = ChunkId { id: *b"LIST" };
+
+/// The maximum nesting depth of `RIFF`/`LIST` containers this crate will descend into: used to
+/// bound `RiffReader::get_chunk_recursive`'s recursion, and by `RiffWriter::begin_list` to size
+/// its fixed, allocation-free bookkeeping of open containers. Chosen generously for typical
+/// formats (WAVE, AVI, WebP) while keeping both bounded for `no_std`/embedded use.
+const MAX_LIST_NESTING: usize = 8;
+
+impl<'a> Chunk<'a> {
+    /// Returns whether this chunk is a container (`RIFF`, `RIFX` or `LIST`) that holds a form
+    /// type followed by nested subchunks, rather than opaque payload data.
+    pub fn has_subchunks(&self) -> bool {
+        self.id == RIFF_ID || self.id == RIFX_ID || self.id == LIST_ID
+    }
+
+    /// Returns the form type / list type of a container chunk, i.e. the 4-byte `ChunkId`
+    /// stored as the first 4 bytes of its payload (e.g. `WAVE` in a `RIFF` chunk).
+    ///
+    /// Returns `None` if this chunk is not a container, or if its form type is not valid ASCII.
+    pub fn form_type(&self) -> Option<ChunkId> {
+        if !self.has_subchunks() {
+            return None;
+        }
+        match read_id_at(self.data, 0) {
+            (_, Ok(id)) => ChunkId::from_ascii(id).ok(),
+            (_, Err(_)) => None,
+        }
+    }
+
+    /// Returns a `RiffReader` over this chunk's nested subchunks, positioned just after the
+    /// form type so callers don't have to slice off the leading 4 bytes themselves.
+    ///
+    /// If this chunk is not a container, the returned reader is empty. Recursing through this
+    /// method repeatedly (as `RiffReader::get_chunk_recursive` does) is bounded to
+    /// `MAX_LIST_NESTING` levels deep; called directly, it carries no such limit.
+    pub fn subchunks(&self) -> RiffReader<'a> {
+        if !self.has_subchunks() {
+            return RiffReader::with_endianness(&self.data[0..0], self.endianness);
+        }
+        let skip = 4.min(self.data.len());
+        RiffReader::with_endianness(&self.data[skip..], self.endianness)
+    }
+}
 
 /// The ID of a RIFF chunk.
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -93,13 +158,35 @@ impl ChunkId {
     }
 }
 
-impl RiffReader<'_> {
+impl<'a> RiffReader<'a> {
     /// Creates a new `RiffReader` over an underlying byte slice.
     ///
+    /// The endianness used to decode chunk lengths is detected from the leading magic bytes:
+    /// `RIFX` selects big-endian, anything else (including the common `RIFF`) selects
+    /// little-endian. Use [`RiffReader::with_endianness`] to bypass detection.
+    ///
     /// Note that this function does not ensure that the underlying pool is valid RIFF data.
     pub fn new(data: &[u8]) -> RiffReader {
-        return RiffReader { data: data, pos: 0 };
+        let endianness = detect_endianness(data);
+        return RiffReader {
+            data: data,
+            pos: 0,
+            endianness,
+        };
+    }
+
+    /// Creates a new `RiffReader` over an underlying byte slice, using the given endianness to
+    /// decode chunk lengths instead of detecting it from the data's leading magic bytes.
+    ///
+    /// Note that this function does not ensure that the underlying pool is valid RIFF data.
+    pub fn with_endianness(data: &[u8], endianness: Endianness) -> RiffReader {
+        return RiffReader {
+            data: data,
+            pos: 0,
+            endianness,
+        };
     }
+
     /// Reads the next chunk out of the underlying byte slice.
     ///
     /// Returns an error if the underlying byte slice is exhausted or invalid data is encountered.
@@ -107,10 +194,11 @@ impl RiffReader<'_> {
     /// For efficiency reasons, the returned `Chunk` contains a reference to the data rather than a copy,
     /// meaning that it cannot live longer than the originating `RiffReader`.
     ///
-    /// This may be turned into an iterator in the future, once the `Item` type of an `Iterator`
-    /// can have an explicit lifetime.
-    pub fn read_next_chunk(&mut self) -> Result<Chunk, RiffError> {
-        let (new_pos, result) = read_chunk_at(self.data, self.pos);
+    /// `RiffReader` also implements `Iterator`, which wraps this and stops cleanly on
+    /// `RiffError::EndOfData` instead of returning it, letting callers `for chunk in reader` or
+    /// use combinators like `find`/`filter`.
+    pub fn read_next_chunk(&mut self) -> Result<Chunk<'a>, RiffError> {
+        let (new_pos, result) = read_chunk_at(self.data, self.pos, self.endianness);
         // Move to next chunk for next call
         self.pos = new_pos;
 
@@ -120,12 +208,12 @@ impl RiffReader<'_> {
     /// Returns the chunk with the given ID, if present.
     /// If not present, returns `None`.
     /// Note that this does not recurse into chunks that can contain other chunks.
-    pub fn get_chunk(&self, wanted_id: ChunkId) -> Option<Result<Chunk, RiffError>> {
+    pub fn get_chunk(&self, wanted_id: ChunkId) -> Option<Result<Chunk<'a>, RiffError>> {
         // TODO: Clean this up so we don't need a mutable reference
         // Iterate over each chunk until either a matching ID or end of data is encountered
         let mut pos: usize = 0;
         loop {
-            let (new_pos, result) = read_chunk_at(self.data, pos);
+            let (new_pos, result) = read_chunk_at(self.data, pos, self.endianness);
             match result {
                 Ok(chunk) => {
                     if chunk.id == wanted_id {
@@ -146,10 +234,142 @@ impl RiffReader<'_> {
             pos = new_pos;
         }
     }
+
+    /// Returns the chunk with the given ID, if present, descending into container chunks
+    /// (`RIFF`/`LIST`) to search their subchunks when it isn't found at the current level.
+    ///
+    /// The first matching chunk found via depth-first search is returned. Descent stops after
+    /// `MAX_LIST_NESTING` levels of nested containers, bounding stack usage on `no_std` targets
+    /// the same way `RiffWriter::begin_list` bounds nesting on the write side.
+    pub fn get_chunk_recursive(&self, wanted_id: ChunkId) -> Option<Result<Chunk<'a>, RiffError>> {
+        self.get_chunk_recursive_at_depth(wanted_id, 0)
+    }
+
+    fn get_chunk_recursive_at_depth(
+        &self,
+        wanted_id: ChunkId,
+        depth: usize,
+    ) -> Option<Result<Chunk<'a>, RiffError>> {
+        let mut pos: usize = 0;
+        loop {
+            let (new_pos, result) = read_chunk_at(self.data, pos, self.endianness);
+            match result {
+                Ok(chunk) => {
+                    if chunk.id == wanted_id {
+                        return Some(Ok(chunk));
+                    }
+                    if chunk.has_subchunks() && depth < MAX_LIST_NESTING {
+                        if let Some(found) =
+                            chunk.subchunks().get_chunk_recursive_at_depth(wanted_id, depth + 1)
+                        {
+                            return Some(found);
+                        }
+                    }
+                }
+                Err(err) => match err {
+                    // Exhausted without having found a matching chunk
+                    RiffError::EndOfData => {
+                        return None;
+                    }
+                    // Other errors are unexpected
+                    _ => {
+                        return Some(Err(err));
+                    }
+                },
+            }
+            pos = new_pos;
+        }
+    }
+}
+
+impl<'a> Iterator for RiffReader<'a> {
+    type Item = Result<Chunk<'a>, RiffError>;
+
+    /// Reads the next chunk, stopping iteration cleanly once `RiffError::EndOfData` is reached.
+    /// Any other error is yielded once as `Some(Err(..))`, same as `read_next_chunk`.
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_next_chunk() {
+            Ok(chunk) => Some(Ok(chunk)),
+            Err(RiffError::EndOfData) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// The concrete file format declared by a top-level RIFF container's form type.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum RiffForm {
+    /// `WAVE`, waveform audio.
+    Wave,
+    /// `AVI `, audio/video interleave.
+    Avi,
+    /// `WEBP`, the WebP image format.
+    WebP,
+    /// Any other form type, carried as-is.
+    Other(ChunkId),
+}
+
+impl RiffForm {
+    fn from_id(id: ChunkId) -> RiffForm {
+        match id.to_ascii() {
+            "WAVE" => RiffForm::Wave,
+            "AVI " => RiffForm::Avi,
+            "WEBP" => RiffForm::WebP,
+            _ => RiffForm::Other(id),
+        }
+    }
+}
+
+/// A validated, self-describing entry point into a top-level RIFF (or RIFX) blob.
+///
+/// Unlike `RiffReader::new`, which performs no validation, `RiffFile::parse` checks that `data`
+/// starts with a well-formed `RIFF`/`RIFX` container whose declared size actually fits within
+/// the slice, following immeta's approach of using RIFF as the substrate for concrete formats.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RiffFile<'a> {
+    /// The concrete format declared by the outer container's form type.
+    pub form: RiffForm,
+    /// A reader positioned over the form's subchunks.
+    pub reader: RiffReader<'a>,
+}
+
+impl<'a> RiffFile<'a> {
+    /// Validates and parses the outer `RIFF`/`RIFX` container of `data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RiffError::NotARiffContainer` if the first chunk isn't a well-formed
+    /// `RIFF`/`RIFX` container (including the case where its declared form type isn't valid
+    /// ASCII), or `RiffError::UnexpectedEndOfData` if its declared size exceeds `data`.
+    /// Other errors from reading the first chunk's header are returned as-is, e.g.
+    /// `RiffError::EndOfData` if `data` is shorter than 8 bytes, or
+    /// `RiffError::EncounteredInvalidIDNotASCII` if its ID is not valid ASCII.
+    pub fn parse(data: &'a [u8]) -> Result<RiffFile<'a>, RiffError> {
+        let endianness = detect_endianness(data);
+        let (_, result) = read_chunk_at(data, 0, endianness);
+        let chunk = result?;
+        if chunk.id != RIFF_ID && chunk.id != RIFX_ID {
+            return Err(RiffError::NotARiffContainer);
+        }
+        let form_type = chunk.form_type().ok_or(RiffError::NotARiffContainer)?;
+        return Ok(RiffFile {
+            form: RiffForm::from_id(form_type),
+            reader: chunk.subchunks(),
+        });
+    }
+}
+
+/// Detects the endianness of a top-level RIFF blob from its leading magic bytes:
+/// `RIFX` means big-endian, anything else (including `RIFF`) means little-endian.
+fn detect_endianness(data: &[u8]) -> Endianness {
+    if data.len() >= 4 && &data[0..4] == b"RIFX" {
+        return Endianness::Big;
+    }
+    return Endianness::Little;
 }
 
 /// Read the chunk starting at byte pos, and also return the position of the next block.
-fn read_chunk_at(data: &[u8], pos: usize) -> (usize, Result<Chunk, RiffError>) {
+fn read_chunk_at(data: &[u8], pos: usize, endianness: Endianness) -> (usize, Result<Chunk, RiffError>) {
     // Roughly, a RIFF file consists of a bunch of chunks,
     // and each chunk consists of a 4 byte ID, 4 byte length to be interpreted as `u32`, and data following which is len bytes in size.
 
@@ -172,7 +392,7 @@ fn read_chunk_at(data: &[u8], pos: usize) -> (usize, Result<Chunk, RiffError>) {
 
     // Read the length
     let len: usize;
-    match read_len_at(data, pos) {
+    match read_len_at(data, pos, endianness) {
         // TODO: Return error if conversion fails instead of panicking
         (new_pos, Ok(val)) => {
             pos = new_pos;
@@ -195,6 +415,7 @@ fn read_chunk_at(data: &[u8], pos: usize) -> (usize, Result<Chunk, RiffError>) {
             data: payload_data,
             id,
             len,
+            endianness,
         }),
     );
 }
@@ -215,7 +436,7 @@ fn read_id_at(data: &[u8], pos: usize) -> (usize, Result<[u8; 4], RiffError>) {
     return (pos, Ok(as_bytes.try_into().unwrap()));
 }
 
-fn read_len_at(data: &[u8], pos: usize) -> (usize, Result<u32, RiffError>) {
+fn read_len_at(data: &[u8], pos: usize, endianness: Endianness) -> (usize, Result<u32, RiffError>) {
     let mut pos = pos;
     // Check whether we can actually read as much in order to prevent a runtime panic due to OOB index
     if (data.len() - pos) < 4 {
@@ -223,31 +444,398 @@ fn read_len_at(data: &[u8], pos: usize) -> (usize, Result<u32, RiffError>) {
     }
     let len_as_bytes = &data[pos..(pos + 4)];
     // This panic will never happen, as we have obtained a subslice of length 4 in previous step
-    let len = u32::from_le_bytes(len_as_bytes.try_into().unwrap());
+    let len = match endianness {
+        Endianness::Little => u32::from_le_bytes(len_as_bytes.try_into().unwrap()),
+        Endianness::Big => u32::from_be_bytes(len_as_bytes.try_into().unwrap()),
+    };
     pos += 4;
     return (pos, Ok(len));
 }
 
 fn read_data_at(data: &[u8], pos: usize, len: usize) -> (usize, Result<&[u8], RiffError>) {
     let mut pos = pos;
-    // Check whether remainder of backing byte slice is large enough
-    if data.len() <= (pos + len) {
-        let retval = Ok(&data[pos..(pos + len)]);
+    // Check whether remainder of backing byte slice is large enough.
+    // Note that a payload reaching exactly to the end of the slice (pos + len == data.len()) is valid.
+    // `pos.checked_add(len)` guards against a data-controlled `len` (up to `u32::MAX`) overflowing
+    // `usize` on 32-bit targets, which would otherwise wrap the sum and let a bogus length slip
+    // past this bounds check.
+    if let Some(end) = pos.checked_add(len).filter(|&end| end <= data.len()) {
+        let retval = Ok(&data[pos..end]);
         pos += len;
         // Note that a padding byte is added if len is odd, meaning we have to advance the position by 1 extra.
-        if (len % 2) != 0 {
+        // Only do so if a physical pad byte is actually present: the last chunk in a slice may
+        // end exactly at `data.len()` with no trailing pad byte to skip over.
+        if (len % 2) != 0 && pos < data.len() {
             pos += 1;
         }
         return (pos, retval);
     }
 
-    // If not, that means we encountered an unexpected end of data
+    // If not, that means we encountered an unexpected end of data.
+    // `data.len() - pos` (rather than `data.len() - (pos + len)`, which would underflow here
+    // since we now know pos + len > data.len()) reports how many bytes are actually available.
     return (
         pos,
         Err(RiffError::UnexpectedEndOfData(
             pos - 4, // To get to starting index of length specifier
             len.try_into().unwrap(),
-            data.len() - (pos + len),
+            data.len() - pos,
         )),
     );
 }
+
+/// Internal state of a [`StreamParser`]'s chunk-parsing state machine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    /// Collecting the 4-byte chunk ID into the scratch buffer.
+    Id,
+    /// Collecting the 4-byte chunk length into the scratch buffer.
+    Len,
+    /// Handing out the chunk's payload, `remaining` bytes of it still outstanding.
+    Body,
+    /// Skipping the single pad byte present when the chunk's length is odd.
+    Pad,
+    /// An unrecoverable error was encountered; further calls to `feed` replay it.
+    Done(RiffError),
+}
+
+/// A single unit of progress made by [`StreamParser::feed`].
+#[derive(Debug, PartialEq)]
+pub enum ParseProgress<'a> {
+    /// No complete event could be produced from the input given so far; feed more.
+    NeedMore,
+    /// The ID and declared payload length of a chunk have just been parsed.
+    ChunkHeader {
+        /// The ID of the chunk.
+        id: ChunkId,
+        /// The declared length of the chunk's payload, in bytes.
+        len: u32,
+    },
+    /// A slice of the current chunk's payload, borrowed directly from the input passed to
+    /// `feed`. A chunk's payload may be split across several `Body` events if it doesn't
+    /// arrive in a single call.
+    Body(&'a [u8]),
+    /// The current chunk, including its pad byte if it had an odd length, has been fully
+    /// consumed. The next call to `feed` starts parsing the following chunk.
+    ChunkEnd,
+}
+
+/// A push-based, zero-copy parser for RIFF chunks arriving incrementally, e.g. a packet at a
+/// time over a socket or out of a DMA buffer, where the full data can't be held in one
+/// contiguous `&[u8]` as `RiffReader` requires.
+///
+/// Input is supplied via repeated calls to [`StreamParser::feed`]; at most 8 bytes (a chunk's
+/// ID and length) are ever buffered internally, so memory use is independent of how much data
+/// is parsed.
+#[derive(Debug, Clone)]
+pub struct StreamParser {
+    state: State,
+    scratch: [u8; 8],
+    scratch_len: usize,
+    id: Option<[u8; 4]>,
+    remaining: u32,
+    pad: bool,
+    endianness: Endianness,
+    // Total number of bytes consumed across all `feed` calls so far, so that errors can report a
+    // real position in the stream rather than just an offset into the current `feed` call.
+    offset: usize,
+}
+
+impl StreamParser {
+    /// Creates a new `StreamParser` that decodes little-endian (`RIFF`) chunk lengths, ready to
+    /// parse the ID of the first chunk in the stream.
+    pub fn new() -> StreamParser {
+        StreamParser::with_endianness(Endianness::Little)
+    }
+
+    /// Creates a new `StreamParser` that decodes chunk lengths using the given endianness.
+    /// Use `Endianness::Big` to parse a `RIFX` stream.
+    pub fn with_endianness(endianness: Endianness) -> StreamParser {
+        return StreamParser {
+            state: State::Id,
+            scratch: [0; 8],
+            scratch_len: 0,
+            id: None,
+            remaining: 0,
+            pad: false,
+            endianness,
+            offset: 0,
+        };
+    }
+
+    /// Feeds a newly-arrived slice of input into the parser.
+    ///
+    /// Returns the number of bytes consumed from the start of `input`, together with the
+    /// `ParseProgress` made during this call. Callers should re-feed whatever of `input` was
+    /// not consumed (plus any newly-arrived data) on the next call, driven by the reported
+    /// `ParseProgress`: keep feeding while `NeedMore` is reported, and stop reading `Body`
+    /// slices once `ChunkEnd` is reported for the chunk of interest.
+    pub fn feed<'a>(&mut self, input: &'a [u8]) -> (usize, Result<ParseProgress<'a>, RiffError>) {
+        let mut i: usize = 0;
+        loop {
+            match self.state {
+                State::Id => {
+                    if !self.fill_scratch(input, &mut i) {
+                        self.offset += i;
+                        return (i, Ok(ParseProgress::NeedMore));
+                    }
+                    let id_bytes: [u8; 4] = self.scratch[0..4].try_into().unwrap();
+                    if !id_bytes.is_ascii() {
+                        // Report a position in the overall stream, not just this call's input,
+                        // mirroring `read_id_at`'s convention of reporting the position just past
+                        // the ID that failed to validate.
+                        let err = RiffError::EncounteredInvalidIDNotASCII(self.offset + i);
+                        self.state = State::Done(err);
+                        self.offset += i;
+                        return (i, Err(err));
+                    }
+                    self.id = Some(id_bytes);
+                    self.scratch_len = 0;
+                    self.state = State::Len;
+                }
+                State::Len => {
+                    if !self.fill_scratch(input, &mut i) {
+                        self.offset += i;
+                        return (i, Ok(ParseProgress::NeedMore));
+                    }
+                    let len_bytes: [u8; 4] = self.scratch[0..4].try_into().unwrap();
+                    let len = match self.endianness {
+                        Endianness::Little => u32::from_le_bytes(len_bytes),
+                        Endianness::Big => u32::from_be_bytes(len_bytes),
+                    };
+                    self.scratch_len = 0;
+                    self.remaining = len;
+                    self.pad = (len % 2) != 0;
+                    self.state = State::Body;
+                    // Never panics, as the ID's ASCII-ness was checked in the `Id` state.
+                    let id = ChunkId::from_ascii(self.id.take().unwrap()).unwrap();
+                    self.offset += i;
+                    return (i, Ok(ParseProgress::ChunkHeader { id, len }));
+                }
+                State::Body => {
+                    if self.remaining == 0 {
+                        if self.pad {
+                            // Don't report `ChunkEnd` until the pad byte itself has also been
+                            // consumed, below.
+                            self.state = State::Pad;
+                        } else {
+                            self.state = State::Id;
+                            self.offset += i;
+                            return (i, Ok(ParseProgress::ChunkEnd));
+                        }
+                    } else {
+                        if i >= input.len() {
+                            self.offset += i;
+                            return (i, Ok(ParseProgress::NeedMore));
+                        }
+                        let available = (input.len() - i) as u32;
+                        let take = available.min(self.remaining) as usize;
+                        let body = &input[i..(i + take)];
+                        i += take;
+                        self.remaining -= take as u32;
+                        self.offset += i;
+                        return (i, Ok(ParseProgress::Body(body)));
+                    }
+                }
+                State::Pad => {
+                    if i >= input.len() {
+                        self.offset += i;
+                        return (i, Ok(ParseProgress::NeedMore));
+                    }
+                    i += 1;
+                    self.state = State::Id;
+                    self.offset += i;
+                    return (i, Ok(ParseProgress::ChunkEnd));
+                }
+                State::Done(err) => {
+                    return (i, Err(err));
+                }
+            }
+        }
+    }
+
+    /// Fills the scratch buffer from `input`, advancing `i` as bytes are consumed.
+    /// Returns whether the scratch buffer now holds a full 4-byte ID or length field.
+    fn fill_scratch(&mut self, input: &[u8], i: &mut usize) -> bool {
+        while self.scratch_len < 4 && *i < input.len() {
+            self.scratch[self.scratch_len] = input[*i];
+            self.scratch_len += 1;
+            *i += 1;
+        }
+        self.scratch_len == 4
+    }
+}
+
+impl Default for StreamParser {
+    fn default() -> Self {
+        StreamParser::new()
+    }
+}
+
+/// Error returned by `RiffWriter` when a chunk cannot be written as requested.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum WriteError {
+    /// The output buffer does not have enough remaining space for the data being written.
+    BufferTooSmall,
+    /// `end_list` was called without a matching, still-open `begin_list`.
+    NoOpenList,
+    /// `begin_list` was called while already nested `MAX_LIST_NESTING` containers deep.
+    ListNestingTooDeep,
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use WriteError::*;
+        match self {
+            BufferTooSmall => write!(f, "Output buffer has insufficient remaining space"),
+            NoOpenList => write!(f, "end_list called without a matching open begin_list"),
+            ListNestingTooDeep => write!(
+                f,
+                "begin_list nesting exceeds the supported maximum of {} containers",
+                MAX_LIST_NESTING
+            ),
+        }
+    }
+}
+
+/// Serializes RIFF chunks into a caller-supplied byte slice.
+///
+/// Mirrors `RiffReader`: it borrows rather than owns its backing storage, performs no
+/// allocation, and is usable on `no_std` targets. Use [`RiffWriter::write_chunk`] for leaf
+/// chunks, and [`RiffWriter::begin_list`]/[`RiffWriter::end_list`] to author `RIFF`/`LIST`
+/// containers, whose length field is back-patched once the container is closed.
+pub struct RiffWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+    endianness: Endianness,
+    // Position of each open container's (not yet known) length field, indexed by nesting depth.
+    list_starts: [usize; MAX_LIST_NESTING],
+    list_depth: usize,
+}
+
+impl<'a> RiffWriter<'a> {
+    /// Creates a new `RiffWriter` that serializes little-endian (`RIFF`) chunks into `buf`.
+    pub fn new(buf: &'a mut [u8]) -> RiffWriter<'a> {
+        RiffWriter::with_endianness(buf, Endianness::Little)
+    }
+
+    /// Creates a new `RiffWriter` that serializes chunks into `buf` using the given endianness.
+    /// Use `Endianness::Big` to author a `RIFX` stream.
+    pub fn with_endianness(buf: &'a mut [u8], endianness: Endianness) -> RiffWriter<'a> {
+        RiffWriter {
+            buf,
+            pos: 0,
+            endianness,
+            list_starts: [0; MAX_LIST_NESTING],
+            list_depth: 0,
+        }
+    }
+
+    /// Writes a single chunk with the given ID and payload, including the mandatory pad byte
+    /// when `data` has odd length.
+    ///
+    /// Returns the number of bytes written (including the ID, length field and any pad byte),
+    /// or `WriteError::BufferTooSmall` if the remaining space in the output buffer isn't enough.
+    pub fn write_chunk(&mut self, id: ChunkId, data: &[u8]) -> Result<usize, WriteError> {
+        let pad = (data.len() % 2) != 0;
+        let needed = 8 + data.len() + if pad { 1 } else { 0 };
+        if self.remaining() < needed {
+            return Err(WriteError::BufferTooSmall);
+        }
+        self.write_id(id);
+        self.write_len(data.len() as u32);
+        self.buf[self.pos..(self.pos + data.len())].copy_from_slice(data);
+        self.pos += data.len();
+        if pad {
+            self.buf[self.pos] = 0;
+            self.pos += 1;
+        }
+        Ok(needed)
+    }
+
+    /// Begins a container chunk holding the given form type, to be closed with `end_list`.
+    /// The first (outermost) container opened is written as `RIFF` (or `RIFX`, per this
+    /// writer's endianness); any container opened while already inside one is written as
+    /// `LIST`, mirroring how `Chunk::has_subchunks` treats all three IDs as containers on the
+    /// reading side.
+    ///
+    /// The container's length field is a placeholder until `end_list` back-patches it.
+    pub fn begin_list(&mut self, form_type: ChunkId) -> Result<(), WriteError> {
+        if self.list_depth >= MAX_LIST_NESTING {
+            return Err(WriteError::ListNestingTooDeep);
+        }
+        // ID + length placeholder + form type
+        if self.remaining() < 12 {
+            return Err(WriteError::BufferTooSmall);
+        }
+        let container_id = if self.list_depth != 0 {
+            LIST_ID
+        } else {
+            match self.endianness {
+                Endianness::Little => RIFF_ID,
+                Endianness::Big => RIFX_ID,
+            }
+        };
+        self.write_id(container_id);
+        let len_pos = self.pos;
+        self.write_len(0);
+        self.write_id(form_type);
+        self.list_starts[self.list_depth] = len_pos;
+        self.list_depth += 1;
+        Ok(())
+    }
+
+    /// Closes the most recently opened container, back-patching its length field with the
+    /// number of bytes written since (its form type plus all nested chunks), and emitting a
+    /// pad byte if that turns out to be odd.
+    pub fn end_list(&mut self) -> Result<(), WriteError> {
+        if self.list_depth == 0 {
+            return Err(WriteError::NoOpenList);
+        }
+        self.list_depth -= 1;
+        let len_pos = self.list_starts[self.list_depth];
+        let len = (self.pos - (len_pos + 4)) as u32;
+        self.patch_len(len_pos, len);
+        if (len % 2) != 0 {
+            if self.remaining() < 1 {
+                return Err(WriteError::BufferTooSmall);
+            }
+            self.buf[self.pos] = 0;
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns the bytes written to the output buffer so far.
+    pub fn written(&self) -> &[u8] {
+        &self.buf[0..self.pos]
+    }
+
+    fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    // Caller must have already checked that at least 4 bytes remain.
+    fn write_id(&mut self, id: ChunkId) {
+        self.buf[self.pos..(self.pos + 4)].copy_from_slice(&id.id);
+        self.pos += 4;
+    }
+
+    // Caller must have already checked that at least 4 bytes remain.
+    fn write_len(&mut self, len: u32) {
+        let bytes = match self.endianness {
+            Endianness::Little => len.to_le_bytes(),
+            Endianness::Big => len.to_be_bytes(),
+        };
+        self.buf[self.pos..(self.pos + 4)].copy_from_slice(&bytes);
+        self.pos += 4;
+    }
+
+    fn patch_len(&mut self, at: usize, len: u32) {
+        let bytes = match self.endianness {
+            Endianness::Little => len.to_le_bytes(),
+            Endianness::Big => len.to_be_bytes(),
+        };
+        self.buf[at..(at + 4)].copy_from_slice(&bytes);
+    }
+}